@@ -0,0 +1,128 @@
+use crate::models::{DirectoryEntry, SortKey};
+use std::cmp::Ordering;
+
+/// ディレクトリ一覧の並び替え比較関数
+///
+/// フォルダは`ascending`に関わらず常にファイルより前に来る。同種同士は
+/// `sort_by`で指定されたキーで比較し、`SortKey::CapturedTime`はEXIF撮影日時が
+/// 無いエントリを`modified_time`で代用する。
+pub fn compare_entries(
+    a: &DirectoryEntry,
+    b: &DirectoryEntry,
+    sort_by: SortKey,
+    ascending: bool,
+) -> Ordering {
+    match (a.is_directory, b.is_directory) {
+        (true, false) => return Ordering::Less,
+        (false, true) => return Ordering::Greater,
+        _ => {}
+    }
+
+    let ordering = match sort_by {
+        SortKey::Name => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+        SortKey::ModifiedTime => a.modified_time.cmp(&b.modified_time),
+        SortKey::CapturedTime => {
+            let a_time = a.captured_time.as_deref().unwrap_or(&a.modified_time);
+            let b_time = b.captured_time.as_deref().unwrap_or(&b.modified_time);
+            a_time.cmp(b_time)
+        }
+        SortKey::FileSize => a.file_size.cmp(&b.file_size),
+    };
+
+    if ascending {
+        ordering
+    } else {
+        ordering.reverse()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(
+        name: &str,
+        is_directory: bool,
+        modified_time: &str,
+        captured_time: Option<&str>,
+        file_size: u64,
+    ) -> DirectoryEntry {
+        DirectoryEntry {
+            name: name.to_string(),
+            path: format!("/photos/{}", name),
+            is_directory,
+            modified_time: modified_time.to_string(),
+            captured_time: captured_time.map(|s| s.to_string()),
+            file_size,
+        }
+    }
+
+    #[test]
+    fn test_directories_sort_before_files_regardless_of_ascending() {
+        let dir = entry("a-folder", true, "2024-01-01T00:00:00+00:00", None, 0);
+        let file = entry("z-file.jpg", false, "2024-01-01T00:00:00+00:00", None, 10);
+
+        assert_eq!(
+            compare_entries(&dir, &file, SortKey::Name, true),
+            Ordering::Less
+        );
+        assert_eq!(
+            compare_entries(&dir, &file, SortKey::Name, false),
+            Ordering::Less
+        );
+    }
+
+    #[test]
+    fn test_sort_by_name() {
+        let a = entry("a.jpg", false, "2024-01-01T00:00:00+00:00", None, 0);
+        let b = entry("b.jpg", false, "2024-01-01T00:00:00+00:00", None, 0);
+
+        assert_eq!(compare_entries(&a, &b, SortKey::Name, true), Ordering::Less);
+        assert_eq!(
+            compare_entries(&a, &b, SortKey::Name, false),
+            Ordering::Greater
+        );
+    }
+
+    #[test]
+    fn test_sort_by_modified_time() {
+        let a = entry("a.jpg", false, "2024-01-01T00:00:00+00:00", None, 0);
+        let b = entry("b.jpg", false, "2024-02-01T00:00:00+00:00", None, 0);
+
+        assert_eq!(
+            compare_entries(&a, &b, SortKey::ModifiedTime, true),
+            Ordering::Less
+        );
+    }
+
+    #[test]
+    fn test_sort_by_file_size() {
+        let a = entry("a.jpg", false, "2024-01-01T00:00:00+00:00", None, 100);
+        let b = entry("b.jpg", false, "2024-01-01T00:00:00+00:00", None, 200);
+
+        assert_eq!(
+            compare_entries(&a, &b, SortKey::FileSize, true),
+            Ordering::Less
+        );
+    }
+
+    #[test]
+    fn test_sort_by_captured_time_falls_back_to_modified_time() {
+        // aは撮影日時を持つ（modified_timeより古い値）
+        let a = entry(
+            "a.jpg",
+            false,
+            "2024-06-01T00:00:00+00:00",
+            Some("2024-01-01T00:00:00"),
+            0,
+        );
+        // bは撮影日時が無いのでmodified_timeで代用される
+        let b = entry("b.jpg", false, "2024-03-01T00:00:00+00:00", None, 0);
+
+        // 撮影日時(2024-01)が更新日時(2024-03)より前なのでaが先
+        assert_eq!(
+            compare_entries(&a, &b, SortKey::CapturedTime, true),
+            Ordering::Less
+        );
+    }
+}
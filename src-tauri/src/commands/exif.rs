@@ -4,8 +4,17 @@ use exif::{In, Tag};
 use std::fs::File;
 use std::io::BufReader;
 use std::path::Path;
+use std::process::Command;
+
+/// `exif`クレートがコンテナ解析に対応していない拡張子
+///
+/// RAW/HEIC/動画は`exiftool`に処理を委譲する。
+const EXIFTOOL_ONLY_EXTENSIONS: &[&str] = &["mov", "mp4", "heic", "cr2", "nef", "arw"];
 
 /// EXIF情報を読み取る
+///
+/// `exif`クレートで読み取れない形式（上記拡張子、またはコンテナ解析に失敗したファイル）は
+/// `exiftool`コマンドにフォールバックする。
 #[tracing::instrument]
 pub fn read_exif(path: &str) -> Result<ExifData> {
     let file_path = Path::new(path);
@@ -15,6 +24,42 @@ pub fn read_exif(path: &str) -> Result<ExifData> {
         return Err(PhotoError::FileNotFound(path.to_string()));
     }
 
+    let prefer_exiftool = file_path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| EXIFTOOL_ONLY_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+        .unwrap_or(false);
+
+    if !prefer_exiftool {
+        match read_exif_native(path) {
+            Ok(data) => return Ok(data),
+            Err(native_err) => {
+                return read_exif_via_exiftool(path).map_err(|_| native_err);
+            }
+        }
+    }
+
+    read_exif_via_exiftool(path)
+}
+
+/// 撮影日時のみを軽量に読み取る（サムネイル生成や他のEXIFタグの抽出は行わない）
+///
+/// フォルダ一覧の並び替えのように大量のファイルを素早く走査したい場合に使う。
+/// `exif`クレートで読み取れないファイルは（`exiftool`を起動せず）単に`None`を返す。
+pub fn read_capture_datetime(path: &str) -> Option<String> {
+    let file = File::open(path).ok()?;
+    let mut bufreader = BufReader::new(file);
+    let exif = exif::Reader::new()
+        .read_from_container(&mut bufreader)
+        .ok()?;
+
+    extract_datetime(&exif)
+}
+
+/// `exif`クレートを使ってEXIF情報を読み取る
+fn read_exif_native(path: &str) -> Result<ExifData> {
+    let file_path = Path::new(path);
+
     // ファイルを開く
     let file = File::open(file_path)
         .map_err(|e| PhotoError::FileReadError(format!("{}: {}", path, e)))?;
@@ -64,6 +109,106 @@ pub fn read_exif(path: &str) -> Result<ExifData> {
     })
 }
 
+/// `exiftool -json -n`を実行してEXIF情報を読み取る
+///
+/// `exiftool`が未インストール、または実行に失敗した場合はファイルシステムの
+/// 更新日時を撮影日時の代わりに使い、それ以外のフィールドは`None`のまま返す。
+fn read_exif_via_exiftool(path: &str) -> Result<ExifData> {
+    let output = Command::new("exiftool").arg("-json").arg("-n").arg(path).output();
+
+    let mut data = match output {
+        Ok(out) if out.status.success() => match parse_exiftool_output(&out.stdout) {
+            Ok(data) => data,
+            Err(e) => {
+                tracing::warn!("exiftoolの出力の解析に失敗しました: {}: {}", path, e);
+                ExifData::empty()
+            }
+        },
+        Ok(out) => {
+            tracing::warn!(
+                "exiftoolがエラー終了しました: {}: {}",
+                path,
+                String::from_utf8_lossy(&out.stderr)
+            );
+            ExifData::empty()
+        }
+        Err(e) => {
+            tracing::warn!(
+                "exiftoolの起動に失敗しました（未インストールの可能性があります）: {}: {}",
+                path,
+                e
+            );
+            ExifData::empty()
+        }
+    };
+
+    if data.datetime.is_none() {
+        data.datetime = datetime_from_filesystem(path);
+    }
+
+    Ok(data)
+}
+
+/// `exiftool -json -n`の出力（JSON配列）を`ExifData`に変換
+fn parse_exiftool_output(stdout: &[u8]) -> std::result::Result<ExifData, String> {
+    let values: Vec<serde_json::Value> =
+        serde_json::from_slice(stdout).map_err(|e| e.to_string())?;
+    let entry = values.first().ok_or("exiftoolの出力が空です")?;
+
+    let lat = entry.get("GPSLatitude").and_then(|v| v.as_f64());
+    let lng = entry.get("GPSLongitude").and_then(|v| v.as_f64());
+    let gps = match (lat, lng) {
+        (Some(lat), Some(lng)) => Some(Gps { lat, lng }),
+        _ => None,
+    };
+
+    let datetime = entry
+        .get("DateTimeOriginal")
+        .or_else(|| entry.get("CreateDate"))
+        .and_then(|v| v.as_str())
+        .map(convert_exif_datetime_str);
+
+    let make = entry
+        .get("Make")
+        .and_then(|v| v.as_str())
+        .map(|s| s.trim().to_string());
+    let model = entry
+        .get("Model")
+        .and_then(|v| v.as_str())
+        .map(|s| s.trim().to_string());
+    let camera = match (make, model) {
+        (Some(make), Some(model)) => Some(CameraInfo { make, model }),
+        _ => None,
+    };
+
+    let width = entry.get("ImageWidth").and_then(|v| v.as_u64()).map(|v| v as u32);
+    let height = entry.get("ImageHeight").and_then(|v| v.as_u64()).map(|v| v as u32);
+    let iso = entry.get("ISO").and_then(|v| v.as_u64()).map(|v| v as u32);
+    let aperture = entry.get("FNumber").and_then(|v| v.as_f64());
+    let shutter_speed = entry.get("ExposureTime").and_then(|v| v.as_f64());
+    let focal_length = entry.get("FocalLength").and_then(|v| v.as_f64());
+
+    Ok(ExifData {
+        gps,
+        datetime,
+        camera,
+        width,
+        height,
+        iso,
+        aperture,
+        shutter_speed,
+        focal_length,
+    })
+}
+
+/// ファイルの最終更新日時をISO 8601形式で取得（EXIFに日時が無い場合の最後の手段）
+fn datetime_from_filesystem(path: &str) -> Option<String> {
+    let metadata = std::fs::metadata(path).ok()?;
+    let modified = metadata.modified().ok()?;
+    let duration = modified.duration_since(std::time::UNIX_EPOCH).ok()?;
+    chrono::DateTime::from_timestamp(duration.as_secs() as i64, 0).map(|dt| dt.to_rfc3339())
+}
+
 /// GPS情報を抽出
 fn extract_gps(exif: &exif::Exif) -> Option<Gps> {
     let lat = extract_gps_coordinate(exif, Tag::GPSLatitude, Tag::GPSLatitudeRef)?;
@@ -108,9 +253,13 @@ fn extract_datetime(exif: &exif::Exif) -> Option<String> {
         .get_field(Tag::DateTimeOriginal, In::PRIMARY)
         .or_else(|| exif.get_field(Tag::DateTime, In::PRIMARY))?;
 
-    let datetime_str = datetime_field.display_value().to_string();
+    Some(convert_exif_datetime_str(
+        &datetime_field.display_value().to_string(),
+    ))
+}
 
-    // EXIF形式 "YYYY:MM:DD HH:MM:SS" を ISO 8601形式 "YYYY-MM-DDTHH:MM:SS" に変換
+/// EXIF形式 "YYYY:MM:DD HH:MM:SS" をISO 8601形式 "YYYY-MM-DDTHH:MM:SS" に変換
+fn convert_exif_datetime_str(datetime_str: &str) -> String {
     let iso_datetime = datetime_str.replace(' ', "T").replace(':', "-");
 
     // 最初の2つのハイフンだけを保持（日付部分）、時刻部分はコロンに戻す
@@ -118,9 +267,9 @@ fn extract_datetime(exif: &exif::Exif) -> Option<String> {
     if parts.len() == 2 {
         let date = parts[0];
         let time = parts[1].replace('-', ":");
-        Some(format!("{}T{}", date, time))
+        format!("{}T{}", date, time)
     } else {
-        Some(datetime_str)
+        datetime_str.to_string()
     }
 }
 
@@ -207,4 +356,36 @@ mod tests {
         assert!(result.is_err());
         assert!(matches!(result.unwrap_err(), PhotoError::FileNotFound(_)));
     }
+
+    #[test]
+    fn test_parse_exiftool_output() {
+        let json = br#"[{
+            "GPSLatitude": 35.6586,
+            "GPSLongitude": 139.7454,
+            "DateTimeOriginal": "2024:05:01 12:34:56",
+            "Make": "Sony",
+            "Model": "ILCE-7M4",
+            "ImageWidth": 4000,
+            "ImageHeight": 3000,
+            "ISO": 100,
+            "FNumber": 2.8,
+            "ExposureTime": 0.004,
+            "FocalLength": 35.0
+        }]"#;
+
+        let data = parse_exiftool_output(json).unwrap();
+        let gps = data.gps.unwrap();
+        assert!((gps.lat - 35.6586).abs() < f64::EPSILON);
+        assert!((gps.lng - 139.7454).abs() < f64::EPSILON);
+        assert_eq!(data.datetime.as_deref(), Some("2024-05-01T12:34:56"));
+        assert_eq!(data.camera.unwrap().model, "ILCE-7M4");
+        assert_eq!(data.width, Some(4000));
+        assert_eq!(data.iso, Some(100));
+    }
+
+    #[test]
+    fn test_parse_exiftool_output_malformed_json() {
+        let result = parse_exiftool_output(b"not json");
+        assert!(result.is_err());
+    }
 }
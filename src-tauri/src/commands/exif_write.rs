@@ -0,0 +1,672 @@
+use crate::error::{PhotoError, Result};
+use crate::models::ExifUpdates;
+use exif::{Context, In, Value};
+use std::path::Path;
+use std::process::Command;
+
+/// GPS座標・撮影日時・作者情報などをEXIFに書き込む/上書きする
+///
+/// JPEGはAPP1/EXIFセグメントをネイティブに組み立て直すことで、他のセグメントと
+/// 画像データを一切変更せずに書き込む。それ以外の形式は`exiftool`に委譲する。
+pub fn write_exif(path: &str, updates: &ExifUpdates) -> Result<()> {
+    let file_path = Path::new(path);
+
+    if !file_path.exists() {
+        return Err(PhotoError::FileNotFound(path.to_string()));
+    }
+
+    let extension = file_path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_lowercase();
+
+    if extension == "jpg" || extension == "jpeg" {
+        write_exif_jpeg(path, updates)
+    } else {
+        write_exif_via_exiftool(path, updates)
+    }
+}
+
+/// JPEGのAPP1/EXIFセグメントを組み立て直してファイルに書き戻す
+fn write_exif_jpeg(path: &str, updates: &ExifUpdates) -> Result<()> {
+    let original = std::fs::read(path)
+        .map_err(|e| PhotoError::FileReadError(format!("{}: {}", path, e)))?;
+
+    let existing = read_existing_exif(path);
+    let new_app1_segment = build_exif_app1_segment(existing.as_ref(), updates);
+
+    let rewritten = splice_app1_segment(&original, &new_app1_segment)
+        .map_err(|e| PhotoError::ExifWriteError(format!("{}: {}", path, e)))?;
+
+    std::fs::write(path, rewritten)
+        .map_err(|e| PhotoError::ExifWriteError(format!("{}: {}", path, e)))?;
+
+    Ok(())
+}
+
+/// 既存のEXIFを（あれば）読み取る。無い/壊れている場合は`None`
+fn read_existing_exif(path: &str) -> Option<exif::Exif> {
+    let file = std::fs::File::open(path).ok()?;
+    let mut bufreader = std::io::BufReader::new(file);
+    exif::Reader::new()
+        .read_from_container(&mut bufreader)
+        .ok()
+}
+
+/// JPEGバイト列の中の既存APP1/EXIFセグメントを新しいものに差し替える
+///
+/// SOIの直後に新しいAPP1を挿入し、既存のAPP1/EXIFセグメント（あれば）だけを
+/// 取り除く。APP0/JFIFなど他のセグメントとスキャンデータはすべてそのまま残す。
+fn splice_app1_segment(original: &[u8], new_app1_segment: &[u8]) -> std::result::Result<Vec<u8>, String> {
+    if original.len() < 2 || original[0] != 0xFF || original[1] != 0xD8 {
+        return Err("JPEGのSOIマーカーが見つかりません".to_string());
+    }
+
+    let mut output = Vec::with_capacity(original.len() + new_app1_segment.len());
+    output.extend_from_slice(&original[0..2]); // SOI
+    output.extend_from_slice(new_app1_segment);
+
+    let mut pos = 2usize;
+    while pos < original.len() {
+        if original[pos] != 0xFF {
+            return Err(format!("マーカーが期待される位置に不正なバイトがあります: {:#x}", pos));
+        }
+        // パディング用の0xFFの連続をスキップ
+        let mut marker_pos = pos + 1;
+        while marker_pos < original.len() && original[marker_pos] == 0xFF {
+            marker_pos += 1;
+        }
+        if marker_pos >= original.len() {
+            break;
+        }
+        let marker = original[marker_pos];
+        let header_len = marker_pos - pos + 1; // 0xFF.. + marker
+
+        if marker == 0xD9 || marker == 0xDA {
+            // EOI、またはSOS（この後はマーカー区切りではないスキャンデータ）
+            // 残り全体をそのままコピーして終了
+            output.extend_from_slice(&original[pos..]);
+            break;
+        }
+
+        if (0xD0..=0xD7).contains(&marker) || marker == 0x01 {
+            // 長さを持たないマーカー（RSTn等）
+            output.extend_from_slice(&original[pos..pos + header_len]);
+            pos += header_len;
+            continue;
+        }
+
+        let length_pos = pos + header_len;
+        if length_pos + 2 > original.len() {
+            return Err("セグメント長の読み取りに失敗しました".to_string());
+        }
+        let length = u16::from_be_bytes([original[length_pos], original[length_pos + 1]]) as usize;
+        let segment_end = length_pos + length;
+        if segment_end > original.len() {
+            return Err("セグメント長が不正です".to_string());
+        }
+
+        let is_exif_app1 = marker == 0xE1
+            && length >= 8
+            && &original[length_pos + 2..length_pos + 8] == b"Exif\0\0";
+
+        if !is_exif_app1 {
+            output.extend_from_slice(&original[pos..segment_end]);
+        }
+
+        pos = segment_end;
+    }
+
+    Ok(output)
+}
+
+/// 1つのTIFFフィールド（IFDエントリ）の値
+enum FieldValue {
+    Ascii(String),
+    Long(u32),
+    Rational(Vec<(u32, u32)>),
+    /// 既存フィールドをそのまま複製したもの（型・個数・バイト列は元のまま）
+    Raw {
+        type_code: u16,
+        count: u32,
+        bytes: Vec<u8>,
+    },
+}
+
+impl FieldValue {
+    fn type_code(&self) -> u16 {
+        match self {
+            FieldValue::Ascii(_) => 2,
+            FieldValue::Long(_) => 4,
+            FieldValue::Rational(_) => 5,
+            FieldValue::Raw { type_code, .. } => *type_code,
+        }
+    }
+
+    fn count(&self) -> u32 {
+        match self {
+            FieldValue::Ascii(s) => s.len() as u32 + 1, // NUL終端を含む
+            FieldValue::Long(_) => 1,
+            FieldValue::Rational(pairs) => pairs.len() as u32,
+            FieldValue::Raw { count, .. } => *count,
+        }
+    }
+
+    fn bytes(&self) -> Vec<u8> {
+        match self {
+            FieldValue::Ascii(s) => {
+                let mut bytes = s.as_bytes().to_vec();
+                bytes.push(0);
+                bytes
+            }
+            FieldValue::Long(v) => v.to_le_bytes().to_vec(),
+            FieldValue::Rational(pairs) => {
+                let mut bytes = Vec::with_capacity(pairs.len() * 8);
+                for (num, den) in pairs {
+                    bytes.extend_from_slice(&num.to_le_bytes());
+                    bytes.extend_from_slice(&den.to_le_bytes());
+                }
+                bytes
+            }
+            FieldValue::Raw { bytes, .. } => bytes.clone(),
+        }
+    }
+
+    /// 既存のTIFFフィールド値を、型・個数・バイト列を保ったまま複製する
+    ///
+    /// リトルエンディアンの生バイト列を組み立て直す（元ファイルがビッグエンディアン
+    /// でも、`exif`クレートが既にネイティブの数値型へ解釈済みのため問題にならない）。
+    /// 解釈できない`Unknown`型、および空の値は複製できないため`None`を返す。
+    fn from_existing(value: &Value) -> Option<FieldValue> {
+        let (type_code, count, bytes): (u16, u32, Vec<u8>) = match value {
+            Value::Byte(v) => (1, v.len() as u32, v.clone()),
+            Value::Ascii(strings) => {
+                let mut bytes = Vec::new();
+                for (i, s) in strings.iter().enumerate() {
+                    if i > 0 {
+                        bytes.push(0);
+                    }
+                    bytes.extend_from_slice(s);
+                }
+                bytes.push(0);
+                (2, bytes.len() as u32, bytes)
+            }
+            Value::Short(v) => (
+                3,
+                v.len() as u32,
+                v.iter().flat_map(|x| x.to_le_bytes()).collect(),
+            ),
+            Value::Long(v) => (
+                4,
+                v.len() as u32,
+                v.iter().flat_map(|x| x.to_le_bytes()).collect(),
+            ),
+            Value::Rational(v) => (
+                5,
+                v.len() as u32,
+                v.iter()
+                    .flat_map(|r| [r.num.to_le_bytes(), r.denom.to_le_bytes()].concat())
+                    .collect(),
+            ),
+            Value::SByte(v) => (6, v.len() as u32, v.iter().map(|x| *x as u8).collect()),
+            Value::Undefined(v, _) => (7, v.len() as u32, v.clone()),
+            Value::SShort(v) => (
+                8,
+                v.len() as u32,
+                v.iter().flat_map(|x| x.to_le_bytes()).collect(),
+            ),
+            Value::SLong(v) => (
+                9,
+                v.len() as u32,
+                v.iter().flat_map(|x| x.to_le_bytes()).collect(),
+            ),
+            Value::SRational(v) => (
+                10,
+                v.len() as u32,
+                v.iter()
+                    .flat_map(|r| [r.num.to_le_bytes(), r.denom.to_le_bytes()].concat())
+                    .collect(),
+            ),
+            Value::Float(v) => (
+                11,
+                v.len() as u32,
+                v.iter().flat_map(|x| x.to_le_bytes()).collect(),
+            ),
+            Value::Double(v) => (
+                12,
+                v.len() as u32,
+                v.iter().flat_map(|x| x.to_le_bytes()).collect(),
+            ),
+            Value::Unknown(_, _, _) => return None,
+        };
+
+        (count > 0).then_some(FieldValue::Raw {
+            type_code,
+            count,
+            bytes,
+        })
+    }
+}
+
+struct Field {
+    tag: u16,
+    value: FieldValue,
+}
+
+/// リトルエンディアンのIFD（エントリ部 + オフセット参照される付加データ部）を組み立てる
+///
+/// `ifd_offset`はTIFFヘッダー先頭からこのIFDまでの絶対オフセット。
+fn encode_ifd(fields: &[Field], ifd_offset: u32) -> (Vec<u8>, Vec<u8>) {
+    let header_size = 2 + fields.len() * 12 + 4;
+    let mut extra_cursor = ifd_offset + header_size as u32;
+
+    let mut entries = Vec::with_capacity(header_size);
+    entries.extend_from_slice(&(fields.len() as u16).to_le_bytes());
+
+    let mut extra = Vec::new();
+
+    for field in fields {
+        let bytes = field.value.bytes();
+
+        entries.extend_from_slice(&field.tag.to_le_bytes());
+        entries.extend_from_slice(&field.value.type_code().to_le_bytes());
+        entries.extend_from_slice(&field.value.count().to_le_bytes());
+
+        if bytes.len() <= 4 {
+            let mut inline = [0u8; 4];
+            inline[..bytes.len()].copy_from_slice(&bytes);
+            entries.extend_from_slice(&inline);
+        } else {
+            entries.extend_from_slice(&extra_cursor.to_le_bytes());
+            extra.extend_from_slice(&bytes);
+            extra_cursor += bytes.len() as u32;
+            if bytes.len() % 2 == 1 {
+                extra.push(0); // ワード境界に揃える
+                extra_cursor += 1;
+            }
+        }
+    }
+
+    entries.extend_from_slice(&0u32.to_le_bytes()); // 次のIFDは無し
+
+    (entries, extra)
+}
+
+fn ifd_total_len(fields: &[Field], ifd_offset: u32) -> u32 {
+    let (entries, extra) = encode_ifd(fields, ifd_offset);
+    (entries.len() + extra.len()) as u32
+}
+
+/// 既存のIFD0/ExifIFD/GPSIFDのうち、指定した`context`に属するフィールドを複製する
+///
+/// `skip_tags`に含まれるタグ番号は複製しない（`ExifUpdates`で上書きされるフィールド。
+/// 上書き側が後から改めて積む）。こうして、更新対象でないタグ（Orientation、ISO感度、
+/// 露出関連、`PixelXDimension`等）は書き込み後もそのまま残る。
+fn copy_existing_fields(
+    existing: Option<&exif::Exif>,
+    context: Context,
+    skip_tags: &[u16],
+) -> Vec<Field> {
+    let Some(exif) = existing else {
+        return Vec::new();
+    };
+
+    exif.fields()
+        .filter(|field| field.ifd_num == In::PRIMARY && field.tag.context() == context)
+        .filter(|field| !skip_tags.contains(&field.tag.number()))
+        .filter_map(|field| {
+            FieldValue::from_existing(&field.value).map(|value| Field {
+                tag: field.tag.number(),
+                value,
+            })
+        })
+        .collect()
+}
+
+/// 十進法の座標を度分秒（分母1000の有理数秒）に変換する
+fn decimal_to_dms(decimal: f64) -> Vec<(u32, u32)> {
+    let decimal = decimal.abs();
+    let degrees = decimal.floor();
+    let minutes_full = (decimal - degrees) * 60.0;
+    let minutes = minutes_full.floor();
+    let seconds = (minutes_full - minutes) * 60.0;
+
+    vec![
+        (degrees as u32, 1),
+        (minutes as u32, 1),
+        ((seconds * 1000.0).round() as u32, 1000),
+    ]
+}
+
+/// ISO 8601形式（"YYYY-MM-DDTHH:MM:SS"）をEXIF形式（"YYYY:MM:DD HH:MM:SS"）に変換
+fn iso_datetime_to_exif(iso: &str) -> String {
+    match iso.split_once('T') {
+        Some((date, time)) => format!("{} {}", date.replace('-', ":"), time),
+        None => iso.to_string(),
+    }
+}
+
+/// 更新内容（と既存値）からAPP1/EXIFセグメント全体のバイト列を組み立てる
+///
+/// 既存のIFD0/ExifIFD/GPSIFDを丸ごと読み込み、`updates`で指定されたフィールドだけを
+/// 上書きする。Orientationや露出情報など、このモジュールが関知しない既存タグも
+/// そのまま引き継がれる。
+fn build_exif_app1_segment(existing: Option<&exif::Exif>, updates: &ExifUpdates) -> Vec<u8> {
+    const TIFF_HEADER_SIZE: u32 = 8;
+    const TAG_DATE_TIME: u16 = 0x0132;
+    const TAG_DATE_TIME_ORIGINAL: u16 = 0x9003;
+    const TAG_ARTIST: u16 = 0x013B;
+    const TAG_IMAGE_DESCRIPTION: u16 = 0x010E;
+    const TAG_EXIF_IFD_POINTER: u16 = 0x8769;
+    const TAG_GPS_IFD_POINTER: u16 = 0x8825;
+    const GPS_COORD_TAGS: [u16; 4] = [1, 2, 3, 4]; // LatitudeRef, Latitude, LongitudeRef, Longitude
+
+    let datetime = updates.datetime.as_deref().map(iso_datetime_to_exif);
+
+    let gps = updates.gps.as_ref().map(|gps| {
+        let lat_ref = if gps.lat >= 0.0 { "N" } else { "S" };
+        let lng_ref = if gps.lng >= 0.0 { "E" } else { "W" };
+        (
+            decimal_to_dms(gps.lat),
+            lat_ref.to_string(),
+            decimal_to_dms(gps.lng),
+            lng_ref.to_string(),
+        )
+    });
+
+    // --- ExifIFD（DateTimeOriginal以外の既存タグはそのまま引き継ぐ）---
+    let exif_skip_tags: &[u16] = if datetime.is_some() {
+        &[TAG_DATE_TIME_ORIGINAL]
+    } else {
+        &[]
+    };
+    let mut exif_ifd_fields = copy_existing_fields(existing, Context::Exif, exif_skip_tags);
+    if let Some(dt) = &datetime {
+        exif_ifd_fields.push(Field {
+            tag: TAG_DATE_TIME_ORIGINAL,
+            value: FieldValue::Ascii(dt.clone()),
+        });
+    }
+
+    // --- GPS IFD（新しい座標があれば緯度経度だけ差し替え、それ以外は既存のまま）---
+    let gps_skip_tags: &[u16] = if gps.is_some() { &GPS_COORD_TAGS } else { &[] };
+    let mut gps_ifd_fields = copy_existing_fields(existing, Context::Gps, gps_skip_tags);
+    if let Some((lat, lat_ref, lng, lng_ref)) = &gps {
+        gps_ifd_fields.push(Field { tag: 1, value: FieldValue::Ascii(lat_ref.clone()) }); // GPSLatitudeRef
+        gps_ifd_fields.push(Field { tag: 2, value: FieldValue::Rational(lat.clone()) }); // GPSLatitude
+        gps_ifd_fields.push(Field { tag: 3, value: FieldValue::Ascii(lng_ref.clone()) }); // GPSLongitudeRef
+        gps_ifd_fields.push(Field { tag: 4, value: FieldValue::Rational(lng.clone()) }); // GPSLongitude
+    }
+
+    // --- IFD0（Artist/ImageDescription/DateTime以外の既存タグはそのまま引き継ぐ）---
+    // ExifIFD/GPSIFDへのオフセットは、各IFDのサイズが確定してから埋める（2パス）。
+    let mut ifd0_skip_tags = vec![TAG_EXIF_IFD_POINTER, TAG_GPS_IFD_POINTER];
+    if updates.artist.is_some() {
+        ifd0_skip_tags.push(TAG_ARTIST);
+    }
+    if updates.description.is_some() {
+        ifd0_skip_tags.push(TAG_IMAGE_DESCRIPTION);
+    }
+    if datetime.is_some() {
+        ifd0_skip_tags.push(TAG_DATE_TIME);
+    }
+
+    let build_ifd0_fields = |exif_ifd_ptr: u32, gps_ifd_ptr: u32| {
+        let mut fields = copy_existing_fields(existing, Context::Tiff, &ifd0_skip_tags);
+        if let Some(artist) = &updates.artist {
+            fields.push(Field { tag: TAG_ARTIST, value: FieldValue::Ascii(artist.clone()) });
+        }
+        if let Some(description) = &updates.description {
+            fields.push(Field {
+                tag: TAG_IMAGE_DESCRIPTION,
+                value: FieldValue::Ascii(description.clone()),
+            });
+        }
+        if let Some(datetime) = &datetime {
+            fields.push(Field { tag: TAG_DATE_TIME, value: FieldValue::Ascii(datetime.clone()) });
+        }
+        if !exif_ifd_fields.is_empty() {
+            fields.push(Field { tag: TAG_EXIF_IFD_POINTER, value: FieldValue::Long(exif_ifd_ptr) });
+        }
+        if !gps_ifd_fields.is_empty() {
+            fields.push(Field { tag: TAG_GPS_IFD_POINTER, value: FieldValue::Long(gps_ifd_ptr) });
+        }
+        fields
+    };
+
+    // 1パス目: オフセットは仮の0でIFD0のサイズを確定させる
+    let ifd0_fields_pass1 = build_ifd0_fields(0, 0);
+    let ifd0_offset = TIFF_HEADER_SIZE;
+    let ifd0_size = ifd_total_len(&ifd0_fields_pass1, ifd0_offset);
+
+    let exif_ifd_offset = ifd0_offset + ifd0_size;
+    let exif_ifd_size = ifd_total_len(&exif_ifd_fields, exif_ifd_offset);
+
+    let gps_ifd_offset = exif_ifd_offset + exif_ifd_size;
+
+    // 2パス目: 確定したオフセットでIFD0を再構築する（サイズはパス1と同一になる）
+    let ifd0_fields = build_ifd0_fields(exif_ifd_offset, gps_ifd_offset);
+    let (ifd0_entries, ifd0_extra) = encode_ifd(&ifd0_fields, ifd0_offset);
+    let (exif_entries, exif_extra) = encode_ifd(&exif_ifd_fields, exif_ifd_offset);
+    let (gps_entries, gps_extra) = encode_ifd(&gps_ifd_fields, gps_ifd_offset);
+
+    let mut tiff_data = Vec::new();
+    tiff_data.extend_from_slice(b"II"); // リトルエンディアン
+    tiff_data.extend_from_slice(&42u16.to_le_bytes());
+    tiff_data.extend_from_slice(&TIFF_HEADER_SIZE.to_le_bytes()); // IFD0オフセット
+    tiff_data.extend_from_slice(&ifd0_entries);
+    tiff_data.extend_from_slice(&ifd0_extra);
+    tiff_data.extend_from_slice(&exif_entries);
+    tiff_data.extend_from_slice(&exif_extra);
+    tiff_data.extend_from_slice(&gps_entries);
+    tiff_data.extend_from_slice(&gps_extra);
+
+    let mut segment = Vec::with_capacity(4 + 6 + tiff_data.len());
+    segment.push(0xFF);
+    segment.push(0xE1);
+    let length = (2 + 6 + tiff_data.len()) as u16;
+    segment.extend_from_slice(&length.to_be_bytes());
+    segment.extend_from_slice(b"Exif\0\0");
+    segment.extend_from_slice(&tiff_data);
+
+    segment
+}
+
+/// JPEG以外の形式、または`exiftool`経由での書き込み
+fn write_exif_via_exiftool(path: &str, updates: &ExifUpdates) -> Result<()> {
+    let mut command = Command::new("exiftool");
+    command.arg("-overwrite_original");
+
+    if let Some(gps) = &updates.gps {
+        let (lat_abs, lat_ref) = if gps.lat >= 0.0 { (gps.lat, "N") } else { (-gps.lat, "S") };
+        let (lng_abs, lng_ref) = if gps.lng >= 0.0 { (gps.lng, "E") } else { (-gps.lng, "W") };
+
+        command.arg(format!("-GPSLatitude={}", lat_abs));
+        command.arg(format!("-GPSLatitudeRef={}", lat_ref));
+        command.arg(format!("-GPSLongitude={}", lng_abs));
+        command.arg(format!("-GPSLongitudeRef={}", lng_ref));
+    }
+
+    if let Some(datetime) = &updates.datetime {
+        command.arg(format!("-DateTimeOriginal={}", iso_datetime_to_exif(datetime)));
+    }
+
+    if let Some(artist) = &updates.artist {
+        command.arg(format!("-Artist={}", artist));
+    }
+
+    if let Some(description) = &updates.description {
+        command.arg(format!("-ImageDescription={}", description));
+    }
+
+    command.arg(path);
+
+    let output = command.output().map_err(|e| {
+        PhotoError::ExifWriteError(format!("exiftoolの起動に失敗しました: {}: {}", path, e))
+    })?;
+
+    if !output.status.success() {
+        return Err(PhotoError::ExifWriteError(format!(
+            "{}: {}",
+            path,
+            String::from_utf8_lossy(&output.stderr)
+        )));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_exif_nonexistent_file() {
+        let result = write_exif("/nonexistent/file.jpg", &ExifUpdates::default());
+        assert!(result.is_err());
+        assert!(matches!(result.unwrap_err(), PhotoError::FileNotFound(_)));
+    }
+
+    #[test]
+    fn test_decimal_to_dms_matches_degrees_minutes() {
+        let dms = decimal_to_dms(35.658581);
+        assert_eq!(dms[0], (35, 1));
+        assert_eq!(dms[1], (39, 1));
+    }
+
+    /// Make/Orientation(IFD0)とISOSpeedRatings(ExifIFD)だけを持つ最小のAPP1セグメントを組み立てる
+    fn fixture_app1_segment() -> Vec<u8> {
+        const TIFF_HEADER_SIZE: u32 = 8;
+
+        let exif_ifd_fields = vec![Field {
+            tag: 0x8827, // ISOSpeedRatings
+            value: FieldValue::Raw {
+                type_code: 3, // SHORT
+                count: 1,
+                bytes: 200u16.to_le_bytes().to_vec(),
+            },
+        }];
+
+        let build_ifd0 = |exif_ifd_ptr: u32| {
+            vec![
+                Field { tag: 0x010F, value: FieldValue::Ascii("Sony".to_string()) }, // Make
+                Field {
+                    tag: 0x0112, // Orientation
+                    value: FieldValue::Raw {
+                        type_code: 3,
+                        count: 1,
+                        bytes: 6u16.to_le_bytes().to_vec(),
+                    },
+                },
+                Field { tag: 0x8769, value: FieldValue::Long(exif_ifd_ptr) }, // ExifIFDPointer
+            ]
+        };
+
+        let ifd0_offset = TIFF_HEADER_SIZE;
+        let ifd0_size = ifd_total_len(&build_ifd0(0), ifd0_offset);
+        let exif_ifd_offset = ifd0_offset + ifd0_size;
+
+        let (ifd0_entries, ifd0_extra) = encode_ifd(&build_ifd0(exif_ifd_offset), ifd0_offset);
+        let (exif_entries, exif_extra) = encode_ifd(&exif_ifd_fields, exif_ifd_offset);
+
+        let mut tiff_data = Vec::new();
+        tiff_data.extend_from_slice(b"II");
+        tiff_data.extend_from_slice(&42u16.to_le_bytes());
+        tiff_data.extend_from_slice(&TIFF_HEADER_SIZE.to_le_bytes());
+        tiff_data.extend_from_slice(&ifd0_entries);
+        tiff_data.extend_from_slice(&ifd0_extra);
+        tiff_data.extend_from_slice(&exif_entries);
+        tiff_data.extend_from_slice(&exif_extra);
+
+        let mut segment = Vec::new();
+        segment.push(0xFF);
+        segment.push(0xE1);
+        let length = (2 + 6 + tiff_data.len()) as u16;
+        segment.extend_from_slice(&length.to_be_bytes());
+        segment.extend_from_slice(b"Exif\0\0");
+        segment.extend_from_slice(&tiff_data);
+        segment
+    }
+
+    fn wrap_in_minimal_jpeg(app1_segment: &[u8]) -> Vec<u8> {
+        let mut bytes = vec![0xFF, 0xD8];
+        bytes.extend_from_slice(app1_segment);
+        bytes.extend_from_slice(&[0xFF, 0xD9]);
+        bytes
+    }
+
+    fn parse_exif(jpeg_bytes: &[u8]) -> exif::Exif {
+        let mut reader = std::io::BufReader::new(std::io::Cursor::new(jpeg_bytes.to_vec()));
+        exif::Reader::new().read_from_container(&mut reader).unwrap()
+    }
+
+    #[test]
+    fn test_build_exif_app1_segment_preserves_unrelated_tags_and_applies_updates() {
+        use crate::models::Gps;
+        use exif::Tag;
+
+        let original = wrap_in_minimal_jpeg(&fixture_app1_segment());
+        let existing = parse_exif(&original);
+
+        let updates = ExifUpdates {
+            gps: Some(Gps { lat: 35.6586, lng: 139.7454 }),
+            datetime: Some("2024-05-01T12:34:56".to_string()),
+            ..ExifUpdates::default()
+        };
+
+        let new_segment = build_exif_app1_segment(Some(&existing), &updates);
+        let result = parse_exif(&wrap_in_minimal_jpeg(&new_segment));
+
+        // 更新対象でない既存タグ(Orientation, ISOSpeedRatings)は保持される
+        assert_eq!(
+            result
+                .get_field(Tag::Orientation, In::PRIMARY)
+                .unwrap()
+                .value
+                .get_uint(0),
+            Some(6)
+        );
+        assert_eq!(
+            result
+                .get_field(Tag::ISOSpeedRatings, In::PRIMARY)
+                .unwrap()
+                .value
+                .get_uint(0),
+            Some(200)
+        );
+
+        // GPS/撮影日時はupdatesの内容で上書きされる
+        match &result.get_field(Tag::GPSLatitude, In::PRIMARY).unwrap().value {
+            Value::Rational(v) => assert_eq!(v[0].to_f64().round(), 35.0),
+            other => panic!("expected rational GPSLatitude, got {:?}", other),
+        }
+        match &result
+            .get_field(Tag::DateTimeOriginal, In::PRIMARY)
+            .unwrap()
+            .value
+        {
+            Value::Ascii(strings) => {
+                assert_eq!(strings[0], b"2024:05:01 12:34:56");
+            }
+            other => panic!("expected ascii DateTimeOriginal, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_splice_app1_segment_inserts_after_soi() {
+        // SOI + APP0(JFIF最小) + EOI
+        let original: Vec<u8> = vec![
+            0xFF, 0xD8, // SOI
+            0xFF, 0xE0, 0x00, 0x04, 0xAA, 0xBB, // APP0 (dummy payload)
+            0xFF, 0xD9, // EOI
+        ];
+        let new_segment: Vec<u8> = vec![0xFF, 0xE1, 0x00, 0x02];
+
+        let result = splice_app1_segment(&original, &new_segment).unwrap();
+        assert_eq!(&result[0..2], &[0xFF, 0xD8]);
+        assert_eq!(&result[2..6], &[0xFF, 0xE1, 0x00, 0x02]);
+        assert_eq!(&result[6..12], &[0xFF, 0xE0, 0x00, 0x04, 0xAA, 0xBB]);
+        assert_eq!(&result[12..], &[0xFF, 0xD9]);
+    }
+}
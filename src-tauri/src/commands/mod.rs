@@ -1,5 +1,13 @@
+pub mod directory;
 pub mod exif;
+pub mod exif_write;
+pub mod photo_data;
 pub mod thumbnail;
+pub mod tracklog;
 
-pub use exif::read_exif;
-pub use thumbnail::generate_thumbnail;
+pub use directory::compare_entries;
+pub use exif::{read_capture_datetime, read_exif};
+pub use exif_write::write_exif;
+pub use photo_data::build_photo_data;
+pub use thumbnail::{clear_thumbnail_cache, generate_thumbnail};
+pub use tracklog::geotag_from_tracklog;
@@ -0,0 +1,64 @@
+use crate::error::{PhotoError, Result};
+use crate::models::{PhotoData, ThumbFormat};
+use std::path::Path;
+
+/// ファイルパスからEXIF情報とサムネイルを含む`PhotoData`を組み立てる
+///
+/// サムネイルは`cache_dir`（呼び出し元が解決したアプリのキャッシュディレクトリ）に
+/// `max_size`/`format`で生成・キャッシュする。`generate_thumbnail`や
+/// `clear_thumbnail_cache`と同じキャッシュ場所・設定を共有するため、これらを
+/// 固定値にせず呼び出し元から受け取る。
+pub fn build_photo_data(
+    path: &str,
+    max_size: u32,
+    format: ThumbFormat,
+    cache_dir: &Path,
+) -> Result<PhotoData> {
+    let file_path = Path::new(path);
+    let metadata = std::fs::metadata(file_path)
+        .map_err(|e| PhotoError::FileReadError(format!("{}: {}", path, e)))?;
+
+    let filename = file_path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("unknown")
+        .to_string();
+
+    let file_size = metadata.len();
+
+    let modified_time = metadata
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| {
+            chrono::DateTime::from_timestamp(d.as_secs() as i64, 0)
+                .map(|dt| dt.to_rfc3339())
+                .unwrap_or_default()
+        })
+        .unwrap_or_default();
+
+    // EXIF情報を読み取る
+    let exif = super::read_exif(path).ok();
+
+    // サムネイルを生成（失敗しても続行）
+    log::info!("サムネイル生成を開始: {}", path);
+    let thumbnail = match super::generate_thumbnail(path, max_size, format, cache_dir) {
+        Ok(thumb) => {
+            log::info!("サムネイル生成成功: 長さ={}", thumb.len());
+            Some(thumb)
+        }
+        Err(e) => {
+            log::error!("サムネイル生成失敗: {}", e);
+            None
+        }
+    };
+
+    Ok(PhotoData {
+        path: path.to_string(),
+        filename,
+        file_size,
+        modified_time,
+        exif,
+        thumbnail,
+    })
+}
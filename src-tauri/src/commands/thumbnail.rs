@@ -1,16 +1,33 @@
 use crate::error::{PhotoError, Result};
+use crate::models::ThumbFormat;
 use base64::{engine::general_purpose, Engine as _};
-use image::{imageops::FilterType, ImageFormat};
+use image::{imageops::FilterType, DynamicImage, ImageFormat};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
 use std::io::Cursor;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
-/// サムネイルの最大サイズ（ピクセル）
-const THUMBNAIL_SIZE: u32 = 200;
+/// サムネイル最大サイズの既定値（ピクセル）
+pub const DEFAULT_THUMBNAIL_SIZE: u32 = 200;
 
-/// 画像ファイルからサムネイルを生成してBase64文字列として返す
-#[tracing::instrument]
-pub fn generate_thumbnail(path: &str) -> Result<String> {
-    tracing::debug!("サムネイル生成開始: {}", path);
+/// AppHandle経由のアプリキャッシュディレクトリを使わない呼び出しが使うフォールバック先
+pub fn default_cache_dir() -> PathBuf {
+    std::env::temp_dir().join("photogeoview").join("thumbnails")
+}
+
+/// 画像ファイルからサムネイルを生成してBase64のData URIとして返す
+///
+/// `(path, mtime, max_size, format)`をキーにディスクキャッシュし、ソースファイルの
+/// 更新日時が変わっていなければ再デコードせずキャッシュ済みのバイト列を返す。
+/// EXIFの`Orientation`タグを読み取り、リサイズ前に回転・反転を適用するので
+/// 縦位置で撮った写真が横倒しで表示されることはない。
+#[tracing::instrument(skip(cache_dir))]
+pub fn generate_thumbnail(
+    path: &str,
+    max_size: u32,
+    format: ThumbFormat,
+    cache_dir: &Path,
+) -> Result<String> {
     let file_path = Path::new(path);
 
     // ファイルの存在確認
@@ -19,6 +36,14 @@ pub fn generate_thumbnail(path: &str) -> Result<String> {
         return Err(PhotoError::FileNotFound(path.to_string()));
     }
 
+    let mtime = file_mtime_seconds(file_path)?;
+    let cache_path = cache_file_path(cache_dir, path, mtime, max_size, format);
+
+    if let Ok(cached) = std::fs::read(&cache_path) {
+        tracing::debug!("サムネイルキャッシュを使用: {}", cache_path.display());
+        return Ok(to_data_uri(&cached, format));
+    }
+
     // 画像を読み込む
     tracing::debug!("画像を読み込み中: {}", path);
     let img = image::open(file_path).map_err(|e| {
@@ -26,30 +51,132 @@ pub fn generate_thumbnail(path: &str) -> Result<String> {
         PhotoError::ImageProcessError(format!("画像の読み込みに失敗: {}", e))
     })?;
 
-    tracing::debug!("画像サイズ: {}x{}", img.width(), img.height());
+    let oriented = apply_orientation(img, read_orientation(file_path));
 
     // アスペクト比を維持してLanczos3でリサイズ
-    let thumbnail = img.resize(THUMBNAIL_SIZE, THUMBNAIL_SIZE, FilterType::Lanczos3);
+    let thumbnail = oriented.resize(max_size, max_size, FilterType::Lanczos3);
 
-    // JPEGとしてメモリにエンコード
-    let mut buffer = Cursor::new(Vec::new());
-    thumbnail
-        .write_to(&mut buffer, ImageFormat::Jpeg)
-        .map_err(|e| {
-            PhotoError::ImageProcessError(format!("サムネイルのエンコードに失敗: {}", e))
+    let encoded = encode_thumbnail(&thumbnail, format)?;
+
+    if let Some(parent) = cache_path.parent() {
+        if let Err(e) = std::fs::create_dir_all(parent) {
+            tracing::warn!("サムネイルキャッシュディレクトリの作成に失敗: {}", e);
+        }
+    }
+    if let Err(e) = std::fs::write(&cache_path, &encoded) {
+        tracing::warn!("サムネイルキャッシュの書き込みに失敗: {}", e);
+    }
+
+    tracing::debug!("サムネイル生成完了: サイズ={}バイト", encoded.len());
+
+    Ok(to_data_uri(&encoded, format))
+}
+
+/// ディスク上のサムネイルキャッシュをすべて削除する
+pub fn clear_thumbnail_cache(cache_dir: &Path) -> Result<()> {
+    if cache_dir.exists() {
+        std::fs::remove_dir_all(cache_dir).map_err(|e| {
+            PhotoError::ImageProcessError(format!("キャッシュの削除に失敗: {}", e))
         })?;
+    }
+    Ok(())
+}
+
+fn to_data_uri(bytes: &[u8], format: ThumbFormat) -> String {
+    format!(
+        "data:{};base64,{}",
+        format.mime_type(),
+        general_purpose::STANDARD.encode(bytes)
+    )
+}
 
-    // Base64エンコード
-    let base64_data = buffer.into_inner();
-    let base64_string = general_purpose::STANDARD.encode(&base64_data);
+fn file_mtime_seconds(path: &Path) -> Result<u64> {
+    let metadata = std::fs::metadata(path)
+        .map_err(|e| PhotoError::FileReadError(format!("{}: {}", path.display(), e)))?;
+    let modified = metadata
+        .modified()
+        .map_err(|e| PhotoError::FileReadError(format!("{}: {}", path.display(), e)))?;
 
-    tracing::debug!("サムネイル生成完了: サイズ={}バイト, Base64長={}", base64_data.len(), base64_string.len());
+    Ok(modified
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0))
+}
 
-    // Data URI形式で返す
-    let data_uri = format!("data:image/jpeg;base64,{}", base64_string);
-    tracing::debug!("Data URI生成完了: 長さ={}", data_uri.len());
+/// `(path, mtime, max_size, format)`をハッシュ化したキャッシュファイルパスを作る
+fn cache_file_path(
+    cache_dir: &Path,
+    path: &str,
+    mtime: u64,
+    max_size: u32,
+    format: ThumbFormat,
+) -> PathBuf {
+    let mut hasher = DefaultHasher::new();
+    path.hash(&mut hasher);
+    mtime.hash(&mut hasher);
+    max_size.hash(&mut hasher);
+    format.cache_key().hash(&mut hasher);
+    let hash = hasher.finish();
 
-    Ok(data_uri)
+    cache_dir.join(format!("{:016x}.{}", hash, format.extension()))
+}
+
+/// EXIFの`Orientation`タグを読み取る（無い/読めない場合は1=正立）
+fn read_orientation(path: &Path) -> u32 {
+    read_orientation_inner(path).unwrap_or(1)
+}
+
+fn read_orientation_inner(path: &Path) -> Option<u32> {
+    let file = std::fs::File::open(path).ok()?;
+    let mut bufreader = std::io::BufReader::new(file);
+    let exif_data = exif::Reader::new()
+        .read_from_container(&mut bufreader)
+        .ok()?;
+
+    exif_data
+        .get_field(exif::Tag::Orientation, exif::In::PRIMARY)?
+        .value
+        .get_uint(0)
+}
+
+/// EXIFの`Orientation`値（1〜8）に従って回転・反転を適用する
+fn apply_orientation(img: DynamicImage, orientation: u32) -> DynamicImage {
+    match orientation {
+        2 => img.fliph(),
+        3 => img.rotate180(),
+        4 => img.flipv(),
+        5 => img.rotate90().fliph(),
+        6 => img.rotate90(),
+        7 => img.rotate270().fliph(),
+        8 => img.rotate270(),
+        _ => img,
+    }
+}
+
+fn encode_thumbnail(img: &DynamicImage, format: ThumbFormat) -> Result<Vec<u8>> {
+    let mut buffer = Cursor::new(Vec::new());
+
+    match format {
+        ThumbFormat::Jpeg { quality } => {
+            let mut encoder =
+                image::codecs::jpeg::JpegEncoder::new_with_quality(&mut buffer, quality);
+            encoder.encode_image(img).map_err(|e| {
+                PhotoError::ImageProcessError(format!("サムネイルのエンコードに失敗: {}", e))
+            })?;
+        }
+        ThumbFormat::Png => {
+            img.write_to(&mut buffer, ImageFormat::Png).map_err(|e| {
+                PhotoError::ImageProcessError(format!("サムネイルのエンコードに失敗: {}", e))
+            })?;
+        }
+        ThumbFormat::WebP => {
+            img.write_to(&mut buffer, ImageFormat::WebP).map_err(|e| {
+                PhotoError::ImageProcessError(format!("サムネイルのエンコードに失敗: {}", e))
+            })?;
+        }
+    }
+
+    Ok(buffer.into_inner())
 }
 
 #[cfg(test)]
@@ -58,8 +185,27 @@ mod tests {
 
     #[test]
     fn test_generate_thumbnail_nonexistent_file() {
-        let result = generate_thumbnail("/nonexistent/file.jpg");
+        let result = generate_thumbnail(
+            "/nonexistent/file.jpg",
+            DEFAULT_THUMBNAIL_SIZE,
+            ThumbFormat::default(),
+            &default_cache_dir(),
+        );
         assert!(result.is_err());
         assert!(matches!(result.unwrap_err(), PhotoError::FileNotFound(_)));
     }
+
+    #[test]
+    fn test_cache_file_path_differs_by_format() {
+        let cache_dir = Path::new("/tmp/photogeoview-test-cache");
+        let jpeg_path = cache_file_path(
+            cache_dir,
+            "/photos/a.jpg",
+            0,
+            200,
+            ThumbFormat::Jpeg { quality: 85 },
+        );
+        let png_path = cache_file_path(cache_dir, "/photos/a.jpg", 0, 200, ThumbFormat::Png);
+        assert_ne!(jpeg_path, png_path);
+    }
 }
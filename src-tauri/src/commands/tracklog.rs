@@ -0,0 +1,297 @@
+use crate::error::{PhotoError, Result};
+use crate::models::{Gps, PhotoData, ThumbFormat};
+use chrono::{DateTime, Utc};
+use std::path::Path;
+
+use super::photo_data::build_photo_data;
+
+/// トラックログ上の1点（時刻, 緯度, 経度）
+pub type TrackPoint = (DateTime<Utc>, f64, f64);
+
+/// 写真時刻がトラック範囲の外側にあり、最寄りの端点との時間差がこの秒数を超える場合、位置推定を諦める
+///
+/// トラック範囲の内側では、前後2点の間隔に関わらず線形補間する。
+const MAX_TRACK_GAP_SECONDS: i64 = 300;
+
+/// GPSを持たない写真を、GPX/PLTトラックログとの時刻照合でジオタグ付けする
+///
+/// 各写真のEXIF撮影日時に`time_offset_seconds`（カメラ時計とGPSロガーのズレ補正）を
+/// 適用し、トラックログ上で前後する2点の間を経過時間で線形補間してGPS座標を求める。
+/// 既にGPSを持つ写真はそのまま、撮影日時が取れない写真やトラック範囲から大きく外れる
+/// 写真はGPSを`None`のまま返す。
+#[tracing::instrument(skip(photo_paths, cache_dir))]
+pub fn geotag_from_tracklog(
+    photo_paths: Vec<String>,
+    track_path: String,
+    time_offset_seconds: i64,
+    max_size: u32,
+    format: ThumbFormat,
+    cache_dir: &Path,
+) -> Result<Vec<PhotoData>> {
+    let track = parse_tracklog(&track_path)?;
+
+    Ok(photo_paths
+        .into_iter()
+        .filter_map(|path| {
+            match build_geotagged_photo_data(
+                &path,
+                &track,
+                time_offset_seconds,
+                max_size,
+                format,
+                cache_dir,
+            ) {
+                Ok(photo_data) => Some(photo_data),
+                Err(e) => {
+                    log::warn!("写真データの読み込みに失敗（スキップ）: {}: {}", path, e);
+                    None
+                }
+            }
+        })
+        .collect())
+}
+
+/// 1枚の写真のEXIF・サムネイルを読み取り、GPSが無ければトラックログから補完する
+fn build_geotagged_photo_data(
+    path: &str,
+    track: &[TrackPoint],
+    time_offset_seconds: i64,
+    max_size: u32,
+    format: ThumbFormat,
+    cache_dir: &Path,
+) -> Result<PhotoData> {
+    let mut photo_data = build_photo_data(path, max_size, format, cache_dir)?;
+
+    if let Some(exif_data) = photo_data.exif.as_mut() {
+        if exif_data.gps.is_none() {
+            if let Some(photo_time) = exif_data
+                .datetime
+                .as_deref()
+                .and_then(parse_photo_datetime)
+            {
+                let adjusted_time = photo_time + chrono::Duration::seconds(time_offset_seconds);
+                exif_data.gps =
+                    interpolate_position(track, adjusted_time, MAX_TRACK_GAP_SECONDS);
+            }
+        }
+    }
+
+    Ok(photo_data)
+}
+
+/// EXIFのISO 8601風撮影日時文字列（タイムゾーン無し）をUTCとして解釈する
+fn parse_photo_datetime(datetime: &str) -> Option<DateTime<Utc>> {
+    chrono::NaiveDateTime::parse_from_str(datetime, "%Y-%m-%dT%H:%M:%S")
+        .ok()
+        .map(|naive| naive.and_utc())
+}
+
+/// GPX（またはPLT）ファイルを読み込み、時刻順のトラックポイント列を返す
+fn parse_tracklog(track_path: &str) -> Result<Vec<TrackPoint>> {
+    let path = Path::new(track_path);
+    if !path.exists() {
+        return Err(PhotoError::FileNotFound(track_path.to_string()));
+    }
+
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_lowercase();
+
+    let mut points = if extension == "plt" {
+        parse_plt(track_path)?
+    } else {
+        parse_gpx(track_path)?
+    };
+
+    points.sort_by_key(|(time, _, _)| *time);
+    Ok(points)
+}
+
+/// GPXの`<trkpt lat=".." lon="..">`要素と子要素`<time>`を読み取る
+fn parse_gpx(track_path: &str) -> Result<Vec<TrackPoint>> {
+    use quick_xml::events::Event;
+    use quick_xml::Reader;
+
+    let content = std::fs::read_to_string(track_path)
+        .map_err(|e| PhotoError::FileReadError(format!("{}: {}", track_path, e)))?;
+
+    let mut reader = Reader::from_str(&content);
+    reader.config_mut().trim_text(true);
+
+    let mut points = Vec::new();
+    let mut buf = Vec::new();
+
+    let mut current: Option<(f64, f64)> = None;
+    let mut in_time = false;
+    let mut time_text = String::new();
+
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(e)) if e.name().as_ref() == b"trkpt" => {
+                let mut lat = None;
+                let mut lng = None;
+                for attr in e.attributes().flatten() {
+                    let value = attr
+                        .decode_and_unescape_value(reader.decoder())
+                        .unwrap_or_default();
+                    match attr.key.as_ref() {
+                        b"lat" => lat = value.parse().ok(),
+                        b"lon" => lng = value.parse().ok(),
+                        _ => {}
+                    }
+                }
+                current = lat.zip(lng);
+            }
+            Ok(Event::Start(e)) if e.name().as_ref() == b"time" => {
+                in_time = true;
+                time_text.clear();
+            }
+            Ok(Event::Text(t)) if in_time => {
+                time_text.push_str(&t.unescape().unwrap_or_default());
+            }
+            Ok(Event::End(e)) if e.name().as_ref() == b"time" => {
+                in_time = false;
+            }
+            Ok(Event::End(e)) if e.name().as_ref() == b"trkpt" => {
+                if let Some((lat, lng)) = current.take() {
+                    if let Ok(time) = DateTime::parse_from_rfc3339(&time_text) {
+                        points.push((time.with_timezone(&Utc), lat, lng));
+                    }
+                }
+            }
+            Ok(Event::Eof) => break,
+            Err(e) => {
+                return Err(PhotoError::InternalError(format!(
+                    "GPXの解析に失敗しました: {}: {}",
+                    track_path, e
+                )))
+            }
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    Ok(points)
+}
+
+/// OziExplorer PLTファイル（6行のヘッダーに続けて`lat,lon,...,date_serial,...`の行）を読み取る
+fn parse_plt(track_path: &str) -> Result<Vec<TrackPoint>> {
+    let content = std::fs::read_to_string(track_path)
+        .map_err(|e| PhotoError::FileReadError(format!("{}: {}", track_path, e)))?;
+
+    let mut points = Vec::new();
+    for line in content.lines().skip(6) {
+        let fields: Vec<&str> = line.split(',').map(|s| s.trim()).collect();
+        if fields.len() < 5 {
+            continue;
+        }
+
+        let lat = fields[0].parse::<f64>();
+        let lng = fields[1].parse::<f64>();
+        let date_serial = fields[4].parse::<f64>();
+
+        if let (Ok(lat), Ok(lng), Ok(date_serial)) = (lat, lng, date_serial) {
+            points.push((ole_date_to_utc(date_serial), lat, lng));
+        }
+    }
+
+    Ok(points)
+}
+
+/// OziExplorerが使うOLEオートメーション日付（1899-12-30起点の日数）をUTC日時に変換
+fn ole_date_to_utc(days: f64) -> DateTime<Utc> {
+    let epoch = DateTime::parse_from_rfc3339("1899-12-30T00:00:00+00:00")
+        .expect("固定のOLEエポック文字列は常にパース可能")
+        .with_timezone(&Utc);
+
+    epoch + chrono::Duration::milliseconds((days * 86_400_000.0).round() as i64)
+}
+
+/// 写真時刻をトラックログ上の2点間で線形補間し、GPS座標を推定する
+///
+/// 写真時刻がトラック範囲の外側にあり、かつ最寄り点との差が`max_gap_seconds`を
+/// 超える場合は`None`を返す。
+fn interpolate_position(
+    track: &[TrackPoint],
+    time: DateTime<Utc>,
+    max_gap_seconds: i64,
+) -> Option<Gps> {
+    if track.is_empty() {
+        return None;
+    }
+
+    let idx = track.partition_point(|(t, _, _)| *t <= time);
+
+    if idx == 0 {
+        let (t0, lat, lng) = track[0];
+        return ((t0 - time).num_seconds().abs() <= max_gap_seconds)
+            .then_some(Gps { lat, lng });
+    }
+
+    if idx == track.len() {
+        let (t_last, lat, lng) = track[track.len() - 1];
+        return ((time - t_last).num_seconds().abs() <= max_gap_seconds)
+            .then_some(Gps { lat, lng });
+    }
+
+    let (t0, lat0, lng0) = track[idx - 1];
+    let (t1, lat1, lng1) = track[idx];
+
+    let span_ms = (t1 - t0).num_milliseconds() as f64;
+    let frac = if span_ms > 0.0 {
+        (time - t0).num_milliseconds() as f64 / span_ms
+    } else {
+        0.0
+    };
+
+    Some(Gps {
+        lat: lat0 + (lat1 - lat0) * frac,
+        lng: lng0 + (lng1 - lng0) * frac,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn track() -> Vec<TrackPoint> {
+        vec![
+            (DateTime::parse_from_rfc3339("2024-05-01T10:00:00Z").unwrap().with_timezone(&Utc), 35.0, 139.0),
+            (DateTime::parse_from_rfc3339("2024-05-01T10:10:00Z").unwrap().with_timezone(&Utc), 35.1, 139.1),
+        ]
+    }
+
+    #[test]
+    fn test_interpolate_position_midpoint() {
+        let time = DateTime::parse_from_rfc3339("2024-05-01T10:05:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let gps = interpolate_position(&track(), time, 300).unwrap();
+        assert!((gps.lat - 35.05).abs() < 1e-9);
+        assert!((gps.lng - 139.05).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_interpolate_position_outside_range_beyond_threshold() {
+        let time = DateTime::parse_from_rfc3339("2024-05-01T09:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        assert!(interpolate_position(&track(), time, 300).is_none());
+    }
+
+    #[test]
+    fn test_geotag_from_tracklog_missing_track_file() {
+        let result = geotag_from_tracklog(
+            vec![],
+            "/nonexistent/track.gpx".to_string(),
+            0,
+            200,
+            crate::models::ThumbFormat::default(),
+            Path::new("/tmp/photogeoview-test-cache"),
+        );
+        assert!(result.is_err());
+        assert!(matches!(result.unwrap_err(), PhotoError::FileNotFound(_)));
+    }
+}
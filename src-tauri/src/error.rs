@@ -21,6 +21,9 @@ pub enum PhotoError {
     #[error("サムネイル生成に失敗しました: {0}")]
     ThumbnailGenerationError(String),
 
+    #[error("EXIF情報の書き込みに失敗しました: {0}")]
+    ExifWriteError(String),
+
     #[error("内部エラー: {0}")]
     InternalError(String),
 }
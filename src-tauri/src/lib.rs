@@ -6,7 +6,19 @@ mod commands;
 mod error;
 mod models;
 
-use crate::models::{DirectoryContent, DirectoryEntry, ExifData, PhotoData};
+use crate::models::{
+    DirectoryContent, DirectoryEntry, ExifData, ExifUpdates, PhotoData, SortKey, ThumbFormat,
+};
+
+/// サムネイルキャッシュの保存先ディレクトリを決定する
+///
+/// アプリのキャッシュディレクトリが取得できない場合は、OS非依存のフォールバック先を使う。
+fn thumbnail_cache_dir(app: &tauri::AppHandle) -> std::path::PathBuf {
+    app.path()
+        .app_cache_dir()
+        .map(|dir| dir.join("thumbnails"))
+        .unwrap_or_else(|_| commands::thumbnail::default_cache_dir())
+}
 
 /// Hello Worldコマンド（テスト用）
 #[command]
@@ -36,7 +48,13 @@ async fn select_photo_files(app: tauri::AppHandle) -> Result<Vec<String>, String
     let file_paths = app
         .dialog()
         .file()
-        .add_filter("Images", &["jpg", "jpeg", "png", "tiff", "tif", "webp"])
+        .add_filter(
+            "Images",
+            &[
+                "jpg", "jpeg", "png", "tiff", "tif", "webp", "mov", "mp4", "heic", "cr2", "nef",
+                "arw",
+            ],
+        )
         .blocking_pick_files();
 
     Ok(file_paths
@@ -65,7 +83,9 @@ async fn scan_folder_for_photos(
     use std::fs;
     use std::path::PathBuf;
 
-    let supported_extensions = ["jpg", "jpeg", "png", "tiff", "tif", "webp"];
+    let supported_extensions = [
+        "jpg", "jpeg", "png", "tiff", "tif", "webp", "mov", "mp4", "heic", "cr2", "nef", "arw",
+    ];
     let mut image_paths = Vec::new();
 
     fn scan_directory(
@@ -119,67 +139,116 @@ async fn read_photo_exif(path: String) -> Result<ExifData, String> {
 
 /// 写真ファイルの基本情報とEXIF情報を取得
 #[command]
-async fn get_photo_data(path: String) -> Result<PhotoData, String> {
-    use std::fs;
+async fn get_photo_data(
+    app: tauri::AppHandle,
+    path: String,
+    max_size: u32,
+    format: ThumbFormat,
+) -> Result<PhotoData, String> {
+    let cache_dir = thumbnail_cache_dir(&app);
+    commands::build_photo_data(&path, max_size, format, &cache_dir).map_err(|e| e.to_string())
+}
+
+/// 写真ファイルのサムネイルを生成
+#[command]
+async fn generate_thumbnail(
+    app: tauri::AppHandle,
+    path: String,
+    max_size: u32,
+    format: ThumbFormat,
+) -> Result<String, String> {
+    let cache_dir = thumbnail_cache_dir(&app);
+    commands::generate_thumbnail(&path, max_size, format, &cache_dir).map_err(|e| e.to_string())
+}
 
-    // ファイルの基本情報を取得
-    let file_path = std::path::Path::new(&path);
-    let metadata = fs::metadata(&path).map_err(|e| format!("ファイル情報の取得に失敗: {}", e))?;
-
-    let filename = file_path
-        .file_name()
-        .and_then(|n| n.to_str())
-        .unwrap_or("unknown")
-        .to_string();
-
-    let file_size = metadata.len();
-
-    let modified_time = metadata
-        .modified()
-        .ok()
-        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
-        .map(|d| {
-            chrono::DateTime::from_timestamp(d.as_secs() as i64, 0)
-                .map(|dt| dt.to_rfc3339())
-                .unwrap_or_default()
+/// サムネイルキャッシュを全削除する
+#[command]
+async fn clear_thumbnail_cache(app: tauri::AppHandle) -> Result<(), String> {
+    let cache_dir = thumbnail_cache_dir(&app);
+    commands::clear_thumbnail_cache(&cache_dir).map_err(|e| e.to_string())
+}
+
+/// フォルダ内の全写真のメタデータ・サムネイルを並列に読み込む
+///
+/// 1枚処理するたびに`photo-loaded`イベントを発行するので、フロントエンドは
+/// 全件の完了を待たずに逐次描画できる。サムネイル生成の失敗は従来どおり
+/// その写真のみ`thumbnail: None`として扱い、フォルダ全体は失敗させない。
+#[command]
+async fn load_folder(
+    app: tauri::AppHandle,
+    folder_path: String,
+    recursive: bool,
+    max_size: u32,
+    format: ThumbFormat,
+) -> Result<Vec<PhotoData>, String> {
+    use rayon::prelude::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tauri::Emitter;
+
+    let paths = scan_folder_for_photos(folder_path.clone(), recursive).await?;
+    let total = paths.len();
+
+    log::info!("フォルダ読み込み開始: {} ({} 個のファイル)", folder_path, total);
+
+    let completed = AtomicUsize::new(0);
+    let cache_dir = thumbnail_cache_dir(&app);
+
+    let photos: Vec<PhotoData> = paths
+        .into_par_iter()
+        .filter_map(|path| match commands::build_photo_data(&path, max_size, format, &cache_dir) {
+            Ok(photo_data) => {
+                let count = completed.fetch_add(1, Ordering::SeqCst) + 1;
+                log::info!("フォルダ読み込み進捗: {}/{}", count, total);
+                let _ = app.emit("photo-loaded", &photo_data);
+                Some(photo_data)
+            }
+            Err(e) => {
+                log::error!("写真データの読み込みに失敗: {}: {}", path, e);
+                None
+            }
         })
-        .unwrap_or_default();
+        .collect();
 
-    // EXIF情報を読み取る
-    let exif = commands::read_exif(&path).ok();
+    log::info!("フォルダ読み込み完了: {} 個中 {} 個を読み込み", total, photos.len());
 
-    // サムネイルを生成（失敗しても続行）
-    log::info!("サムネイル生成を開始: {}", path);
-    let thumbnail = match commands::generate_thumbnail(&path) {
-        Ok(thumb) => {
-            log::info!("サムネイル生成成功: 長さ={}", thumb.len());
-            Some(thumb)
-        }
-        Err(e) => {
-            log::error!("サムネイル生成失敗: {}", e);
-            None
-        }
-    };
-
-    Ok(PhotoData {
-        path: path.clone(),
-        filename,
-        file_size,
-        modified_time,
-        exif,
-        thumbnail,
-    })
+    Ok(photos)
 }
 
-/// 写真ファイルのサムネイルを生成
+/// GPSを持たない写真を、GPX/PLTトラックログとの時刻照合でジオタグ付けする
+#[command]
+async fn geotag_from_tracklog(
+    app: tauri::AppHandle,
+    photo_paths: Vec<String>,
+    track_path: String,
+    time_offset_seconds: i64,
+    max_size: u32,
+    format: ThumbFormat,
+) -> Result<Vec<PhotoData>, String> {
+    let cache_dir = thumbnail_cache_dir(&app);
+    commands::geotag_from_tracklog(
+        photo_paths,
+        track_path,
+        time_offset_seconds,
+        max_size,
+        format,
+        &cache_dir,
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// GPSや撮影日時などのEXIF情報を書き込む/修正する
 #[command]
-async fn generate_thumbnail(path: String) -> Result<String, String> {
-    commands::generate_thumbnail(&path).map_err(|e| e.to_string())
+async fn write_photo_exif(path: String, updates: ExifUpdates) -> Result<(), String> {
+    commands::write_exif(&path, &updates).map_err(|e| e.to_string())
 }
 
 /// ディレクトリの内容を読み取る（フォルダとファイルを両方取得）
 #[command]
-async fn read_directory(path: String) -> Result<DirectoryContent, String> {
+async fn read_directory(
+    path: String,
+    sort_by: SortKey,
+    ascending: bool,
+) -> Result<DirectoryContent, String> {
     use std::fs;
     use std::path::PathBuf;
 
@@ -243,23 +312,25 @@ async fn read_directory(path: String) -> Result<DirectoryContent, String> {
             })
             .unwrap_or_default();
 
+        // 撮影日時を取得（画像ファイルのみ、軽量なEXIF読み取り）
+        let captured_time = if is_directory {
+            None
+        } else {
+            commands::read_capture_datetime(&entry_path.to_string_lossy())
+        };
+
         entries.push(DirectoryEntry {
             name,
             path: entry_path.to_string_lossy().to_string(),
             is_directory,
             modified_time,
+            captured_time,
             file_size,
         });
     }
 
-    // エントリをソート（フォルダを先に、その後ファイルを名前順）
-    entries.sort_by(|a, b| {
-        match (a.is_directory, b.is_directory) {
-            (true, false) => std::cmp::Ordering::Less,
-            (false, true) => std::cmp::Ordering::Greater,
-            _ => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
-        }
-    });
+    // エントリをソート（フォルダを先に、その後指定されたキーで並び替え）
+    entries.sort_by(|a, b| commands::compare_entries(a, b, sort_by, ascending));
 
     log::info!(
         "ディレクトリ読み取り完了: {} ({} フォルダ, {} ファイル)",
@@ -335,7 +406,11 @@ pub fn run() {
             read_photo_exif,
             get_photo_data,
             generate_thumbnail,
-            read_directory
+            read_directory,
+            geotag_from_tracklog,
+            write_photo_exif,
+            load_folder,
+            clear_thumbnail_cache
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -41,6 +41,23 @@ pub struct ExifData {
     pub focal_length: Option<f64>,
 }
 
+impl ExifData {
+    /// 全フィールドが`None`の空のEXIFデータを作成
+    pub fn empty() -> Self {
+        Self {
+            gps: None,
+            datetime: None,
+            camera: None,
+            width: None,
+            height: None,
+            iso: None,
+            aperture: None,
+            shutter_speed: None,
+            focal_length: None,
+        }
+    }
+}
+
 /// 写真データ
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PhotoData {
@@ -106,6 +123,84 @@ pub struct DirectoryEntry {
     pub file_size: u64,
 }
 
+/// サムネイルの出力フォーマット
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum ThumbFormat {
+    /// JPEG（`quality`は1〜100）
+    #[serde(rename = "jpeg")]
+    Jpeg { quality: u8 },
+    /// PNG
+    #[serde(rename = "png")]
+    Png,
+    /// WebP
+    #[serde(rename = "webp")]
+    WebP,
+}
+
+impl Default for ThumbFormat {
+    fn default() -> Self {
+        ThumbFormat::Jpeg { quality: 85 }
+    }
+}
+
+impl ThumbFormat {
+    /// Data URIに使うMIMEタイプ
+    pub fn mime_type(self) -> &'static str {
+        match self {
+            ThumbFormat::Jpeg { .. } => "image/jpeg",
+            ThumbFormat::Png => "image/png",
+            ThumbFormat::WebP => "image/webp",
+        }
+    }
+
+    /// キャッシュファイルの拡張子
+    pub fn extension(self) -> &'static str {
+        match self {
+            ThumbFormat::Jpeg { .. } => "jpg",
+            ThumbFormat::Png => "png",
+            ThumbFormat::WebP => "webp",
+        }
+    }
+
+    /// キャッシュキーに含める設定値の文字列表現（JPEGは品質ごとに別キャッシュにする）
+    pub fn cache_key(self) -> String {
+        match self {
+            ThumbFormat::Jpeg { quality } => format!("jpeg-{}", quality),
+            ThumbFormat::Png => "png".to_string(),
+            ThumbFormat::WebP => "webp".to_string(),
+        }
+    }
+}
+
+/// EXIF書き込み時に上書きする項目（`None`の項目は変更しない）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExifUpdates {
+    /// 新しいGPS座標
+    pub gps: Option<Gps>,
+    /// 新しい撮影日時（ISO 8601形式、"YYYY-MM-DDTHH:MM:SS"）
+    pub datetime: Option<String>,
+    /// 新しい作者名
+    pub artist: Option<String>,
+    /// 新しい説明文
+    pub description: Option<String>,
+}
+
+/// ディレクトリ一覧の並び替えキー
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SortKey {
+    /// 名前順
+    Name,
+    /// 最終更新日時順
+    ModifiedTime,
+    /// 撮影日時順
+    CapturedTime,
+    /// ファイルサイズ順
+    FileSize,
+}
+
 /// ディレクトリの内容
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]